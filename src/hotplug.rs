@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use rusb::{Context, Device, Hotplug, HotplugBuilder, UsbContext};
+
+use super::constants::{PRODUCT_ID, VENDOR_ID};
+use super::{BlinkError, Blinker};
+
+/// Identifies a USB device by its bus number and device address, stable for as long as the device
+/// stays attached. Used by [`HotplugEvent::Left`] since an unplugged device can no longer be
+/// opened to read back its serial number.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceId {
+  bus_number: u8,
+  address: u8,
+}
+
+impl DeviceId {
+  fn from_device(device: &Device<Context>) -> Self {
+    DeviceId {
+      bus_number: device.bus_number(),
+      address: device.address(),
+    }
+  }
+}
+
+/// An event delivered by [`Blinkers::watch`](super::Blinkers::watch) as Blink1 devices are
+/// plugged in or removed at runtime.
+#[derive(Debug)]
+pub enum HotplugEvent {
+  /// A Blink1 device was plugged in
+  Arrived(Blinker),
+  /// A Blink1 device was unplugged
+  Left(DeviceId),
+}
+
+struct Callback<F> {
+  callback: F,
+}
+
+impl<F> Hotplug<Context> for Callback<F>
+where
+  F: FnMut(HotplugEvent) + Send,
+{
+  fn device_arrived(&mut self, device: Device<Context>) {
+    if let Ok(blinker) = Blinker::new(device) {
+      (self.callback)(HotplugEvent::Arrived(blinker));
+    }
+  }
+
+  fn device_left(&mut self, device: Device<Context>) {
+    (self.callback)(HotplugEvent::Left(DeviceId::from_device(&device)));
+  }
+}
+
+/// A handle to the background thread started by [`Blinkers::watch`](super::Blinkers::watch).
+/// Dropping it, or calling [`Watcher::stop`] explicitly, shuts the hotplug event loop down
+/// cleanly.
+pub struct Watcher {
+  running: Arc<AtomicBool>,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+  /// Stop watching for hotplug events and wait for the background thread to exit
+  pub fn stop(mut self) {
+    self.shutdown();
+  }
+
+  fn shutdown(&mut self) {
+    self.running.store(false, Ordering::SeqCst);
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+impl Drop for Watcher {
+  fn drop(&mut self) {
+    self.shutdown();
+  }
+}
+
+pub(crate) fn watch<F>(context: &Context, callback: F) -> Result<Watcher, BlinkError>
+where
+  F: FnMut(HotplugEvent) + Send + 'static,
+{
+  if !rusb::has_hotplug() {
+    return Err(BlinkError::NotFound);
+  }
+
+  let registration = HotplugBuilder::new()
+    .vendor_id(VENDOR_ID)
+    .product_id(PRODUCT_ID)
+    .enumerate(true)
+    .register(context.clone(), Box::new(Callback { callback }))?;
+
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let thread_context = context.clone();
+
+  let handle = thread::spawn(move || {
+    while thread_running.load(Ordering::SeqCst) {
+      let _ = thread_context.handle_events(Some(Duration::from_millis(100)));
+    }
+    drop(registration);
+  });
+
+  Ok(Watcher {
+    running,
+    handle: Some(handle),
+  })
+}
+
+/// Like [`watch`], but forwards each [`HotplugEvent`] over an [`mpsc`] channel instead of a
+/// closure, for callers that would rather poll a [`Receiver`](mpsc::Receiver).
+pub(crate) fn watch_channel(context: &Context) -> Result<(Watcher, mpsc::Receiver<HotplugEvent>), BlinkError> {
+  let (tx, rx) = mpsc::channel();
+  let watcher = watch(context, move |event| {
+    let _ = tx.send(event);
+  })?;
+
+  Ok((watcher, rx))
+}
@@ -32,14 +32,19 @@ use std::fmt;
 use std::time::Duration;
 
 pub use color::Color;
-use constants::{HID_FEATURE, HID_SET_REPORT, PRODUCT_ID, VENDOR_ID};
+use constants::{HID_FEATURE, HID_GET_REPORT, HID_SET_REPORT, PRODUCT_ID, VENDOR_ID};
 pub use error::BlinkError;
 pub use message::LedNum;
 pub use message::Message;
+pub use message::Query;
+pub use message::QueryResponse;
+
+pub use hotplug::{DeviceId, HotplugEvent, Watcher};
 
 mod color;
 mod constants;
 mod error;
+mod hotplug;
 mod message;
 
 fn is_blinker(device: &Device<Context>) -> bool {
@@ -54,18 +59,130 @@ fn send(device: &Device<Context>, message: &Message) -> Result<usize, BlinkError
   let mut handle: DeviceHandle<Context> = device.open()?;
   let interface_num = config.interfaces().nth(0).ok_or(BlinkError::NotFound)?.number();
 
-  if let Ok(active) = handle.kernel_driver_active(interface_num) {
-    if active {
-      handle.detach_kernel_driver(interface_num)?;
-    }
+  let kernel_driver_was_active = handle.kernel_driver_active(interface_num).unwrap_or(false);
+  if kernel_driver_was_active {
+    handle.detach_kernel_driver(interface_num)?;
   }
 
+  // If claiming fails, still attempt the reattach so a detached kernel driver isn't abandoned
+  let claim_result = handle.claim_interface(interface_num).map_err(BlinkError::from);
+  let claim_reattach_result = if claim_result.is_err() && kernel_driver_was_active {
+    handle.attach_kernel_driver(interface_num).map_err(BlinkError::from)
+  } else {
+    Ok(())
+  };
+
+  claim_result?;
+  claim_reattach_result?;
+
   let buffer = message.buffer();
   let time = Duration::new(0, 100);
   let r_type = request_type(Direction::Out, RequestType::Class, Recipient::Interface);
   let request_value: u16 = HID_FEATURE | (buffer[0] as u16);
-  let out = handle.write_control(r_type, HID_SET_REPORT, request_value, 0x00, &buffer, time);
-  out.map_err(|e| BlinkError::from(e))
+  let out = handle
+    .write_control(r_type, HID_SET_REPORT, request_value, 0x00, &buffer, time)
+    .map_err(BlinkError::from);
+
+  // Attempt the reattach regardless of whether releasing the interface succeeded, so a failed
+  // release can't leave the kernel driver permanently detached
+  let release_result = handle.release_interface(interface_num).map_err(BlinkError::from);
+  let reattach_result = if kernel_driver_was_active {
+    handle.attach_kernel_driver(interface_num).map_err(BlinkError::from)
+  } else {
+    Ok(())
+  };
+
+  release_result?;
+  reattach_result?;
+
+  out
+}
+
+fn query(device: &Device<Context>, query: &Query) -> Result<QueryResponse, BlinkError> {
+  let config = device.active_config_descriptor()?;
+  let mut handle: DeviceHandle<Context> = device.open()?;
+  let interface_num = config.interfaces().nth(0).ok_or(BlinkError::NotFound)?.number();
+
+  let kernel_driver_was_active = handle.kernel_driver_active(interface_num).unwrap_or(false);
+  if kernel_driver_was_active {
+    handle.detach_kernel_driver(interface_num)?;
+  }
+
+  // If claiming fails, still attempt the reattach so a detached kernel driver isn't abandoned
+  let claim_result = handle.claim_interface(interface_num).map_err(BlinkError::from);
+  let claim_reattach_result = if claim_result.is_err() && kernel_driver_was_active {
+    handle.attach_kernel_driver(interface_num).map_err(BlinkError::from)
+  } else {
+    Ok(())
+  };
+
+  claim_result?;
+  claim_reattach_result?;
+
+  let buffer = query.buffer();
+  let time = Duration::new(0, 100);
+  let request_value: u16 = HID_FEATURE | (buffer[0] as u16);
+
+  let out_type = request_type(Direction::Out, RequestType::Class, Recipient::Interface);
+  let mut reply = [0u8; 8];
+  let out = handle
+    .write_control(out_type, HID_SET_REPORT, request_value, 0x00, &buffer, time)
+    .map_err(BlinkError::from)
+    .and_then(|_| {
+      let in_type = request_type(Direction::In, RequestType::Class, Recipient::Interface);
+      handle
+        .read_control(in_type, HID_GET_REPORT, request_value, 0x00, &mut reply, time)
+        .map_err(BlinkError::from)
+    });
+
+  // Attempt the reattach regardless of whether releasing the interface succeeded, so a failed
+  // release can't leave the kernel driver permanently detached
+  let release_result = handle.release_interface(interface_num).map_err(BlinkError::from);
+  let reattach_result = if kernel_driver_was_active {
+    handle.attach_kernel_driver(interface_num).map_err(BlinkError::from)
+  } else {
+    Ok(())
+  };
+
+  out?;
+  release_result?;
+  reattach_result?;
+
+  Ok(query.decode(reply))
+}
+
+/// A handle to a single blink(1) device, identified by the serial number reported by its USB
+/// string descriptor. Returned by [`Blinkers::devices`] so a caller can target one specific unit
+/// when several are attached, rather than broadcasting with [`Blinkers::send`].
+pub struct Blinker {
+  device: Device<Context>,
+  serial: String,
+}
+
+impl Blinker {
+  pub(crate) fn new(device: Device<Context>) -> Result<Self, BlinkError> {
+    let descriptor = device.device_descriptor()?;
+    let handle = device.open()?;
+    let serial = handle.read_serial_number_string_ascii(&descriptor)?;
+
+    Ok(Blinker { device, serial })
+  }
+
+  /// The serial number reported by this device's USB string descriptor
+  pub fn serial(&self) -> &str {
+    &self.serial
+  }
+
+  /// Sends the message to this specific device
+  pub fn send(&self, cmd: Message) -> Result<usize, BlinkError> {
+    send(&self.device, &cmd)
+  }
+}
+
+impl fmt::Debug for Blinker {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Blinker {{ serial: {:?} }}", self.serial)
+  }
 }
 
 /// Wraps the [`rusb::Context`](rusb::Context) type.
@@ -106,4 +223,48 @@ impl Blinkers {
     let devices = self.context.devices()?;
     Ok(devices.iter().filter(is_blinker).count())
   }
+
+  /// Enumerate the currently attached Blink1 devices, so one can be selected and addressed
+  /// individually via [`Blinker::send`] instead of broadcasting to every device
+  pub fn devices(&self) -> Result<Vec<Blinker>, BlinkError> {
+    let devices = self.context.devices()?;
+    devices.iter().filter(is_blinker).map(Blinker::new).collect()
+  }
+
+  /// Reads state back from the first attached Blink1 device, such as its firmware version or the
+  /// color currently loaded on a given LED
+  pub fn query(&self, q: Query) -> Result<QueryResponse, BlinkError> {
+    let devices = self.context.devices()?;
+    let device = devices.iter().find(is_blinker).ok_or(BlinkError::NotFound)?;
+
+    query(&device, &q)
+  }
+
+  /// Watch for Blink1 devices being plugged in or unplugged, invoking `callback` for each
+  /// [`HotplugEvent`] from a background thread. Returns a [`Watcher`] that stops the thread when
+  /// dropped or when [`Watcher::stop`] is called.
+  pub fn watch<F>(&self, callback: F) -> Result<Watcher, BlinkError>
+  where
+    F: FnMut(HotplugEvent) + Send + 'static,
+  {
+    hotplug::watch(&self.context, callback)
+  }
+
+  /// Like [`Blinkers::watch`], but delivers events over an
+  /// [`mpsc::Receiver`](std::sync::mpsc::Receiver) instead of a closure
+  pub fn watch_channel(&self) -> Result<(Watcher, std::sync::mpsc::Receiver<HotplugEvent>), BlinkError> {
+    hotplug::watch_channel(&self.context)
+  }
+
+  /// Re-arm the server-tickle watchdog on all attached devices with the given timeout, so a
+  /// monitoring loop can let the light change on its own if it stops calling this in time
+  pub fn tickle(&self, timeout: Duration) -> Result<usize, BlinkError> {
+    self.send(Message::ServerTickle {
+      on: true,
+      timeout,
+      stay_lit: false,
+      start_pos: 0,
+      end_pos: 0,
+    })
+  }
 }
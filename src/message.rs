@@ -36,7 +36,9 @@ pub enum Message {
   Fade(Color, Duration, LedNum),
   /// Set the LED(s) to the specified color without fading
   Immediate(Color),
-  /// Set the color line pattern for the given position [position is the untyped u8]
+  /// Write a single line of the RAM pattern at the given position [position is the untyped u8].
+  /// Lines written this way are lost on power cycle unless persisted with
+  /// [`Message::SaveToFlash`]
   SetLinePattern(Color, Duration, u8),
   /// Set the Led Num. Doing this command BEFORE the SetLinePattern command will make the following
   /// line patterns use only the specified led. This lasts until it is set again
@@ -53,6 +55,30 @@ pub enum Message {
     /// How many times to loop over the animation
     loop_count: u8,
   },
+  /// Persist the RAM pattern written with [`Message::SetLinePattern`] to the device's
+  /// non-volatile flash via action `0x57` ('W'), using the magic bytes the blink1 firmware
+  /// expects for this command, so the pattern survives a power cycle
+  SaveToFlash,
+  /// Mark the last line used by the stored pattern, so [`Message::PlayLoop`] can loop over it
+  /// without the caller needing to separately track how many lines were written
+  SetPatternEnd(u8),
+  /// Arms or disarms the blink1 "server tickle" watchdog via action `0x44` ('D'). While armed,
+  /// the device automatically plays the loaded pattern if the host doesn't re-send this message
+  /// within `timeout`, turning the blink(1) into a dead-man's-switch indicator for a live
+  /// connection or process. See [`Blinkers::tickle`](crate::Blinkers::tickle) for a convenience
+  /// that re-arms it
+  ServerTickle {
+    /// Whether to arm (`true`) or disarm (`false`) the watchdog
+    on: bool,
+    /// How long the device waits for the next tickle before assuming the host has died
+    timeout: Duration,
+    /// Whether the device should stay lit, rather than turn off, once the watchdog trips
+    stay_lit: bool,
+    /// The position in the pattern to start playing from once the watchdog trips
+    start_pos: u8,
+    /// The position in the pattern to stop playing at once the watchdog trips
+    end_pos: u8,
+  },
 }
 
 impl Message {
@@ -94,6 +120,25 @@ impl Message {
 
         [0x01, 112, on_u8, start_pos, end_pos, loop_count, 0, 0]
       }
+      Message::SaveToFlash => [0x01, 0x57, 0xBE, 0xEF, 0xCA, 0xFE, 0x00, 0x00],
+      &Message::SetPatternEnd(pos) => [0x01, 0x4C, pos, 0, 0, 0, 0, 0],
+      &Message::ServerTickle {
+        on,
+        timeout,
+        stay_lit,
+        start_pos,
+        end_pos,
+      } => {
+        let on_u8 = if on { 1 } else { 0 };
+        let stay_lit_u8 = if stay_lit { 1 } else { 0 };
+
+        // divide by 10 and split into hi/lo bytes
+        let dms = timeout.as_millis().checked_div(10).unwrap_or(0) as u16;
+        let th = dms.checked_shr(8).unwrap_or(0) as u8;
+        let tl = (dms & 0xff) as u8;
+
+        [0x01, 0x44, on_u8, th, tl, stay_lit_u8, start_pos, end_pos]
+      }
     }
   }
 }
@@ -104,9 +149,77 @@ impl From<&str> for Message {
   }
 }
 
+/// Represents a read-back request supported by the blink1's `HID_GET_REPORT` handshake, paralleling
+/// the write-only commands in [`Message`]. A `Query` is first written to the device like a
+/// [`Message`], then the feature report is read back and decoded with [`Query::decode`].
+#[derive(Debug, Copy, Clone)]
+pub enum Query {
+  /// Ask the device for its firmware version
+  Version,
+  /// Ask the device for the color currently loaded on the given LED
+  Color(LedNum),
+  /// Ask the device for the color and fade time stored at the given pattern line
+  PatternLine(u8),
+  /// Ask the device how many lines of the pattern are currently marked as in use, i.e. the
+  /// position last set with [`Message::SetPatternEnd`]
+  PatternCount,
+}
+
+impl Query {
+  /// Returns the buffer that should be written to the device before reading the feature report
+  /// back, based on the specification outlined in the [blink1 docs](https://git.io/JenDr).
+  pub fn buffer(&self) -> [u8; 8] {
+    match self {
+      Query::Version => [0x01, 0x76, 0, 0, 0, 0, 0, 0],
+      &Query::Color(ledn) => [0x01, 0x72, 0, 0, 0, 0, 0, ledn.as_u8()],
+      &Query::PatternLine(pos) => [0x01, 0x52, 0, 0, 0, 0, 0, pos],
+      Query::PatternCount => [0x01, 0x4C, 0, 0, 0, 0, 0, 0],
+    }
+  }
+
+  /// Decodes the feature report returned by the device into a typed [`QueryResponse`]
+  pub fn decode(&self, buffer: [u8; 8]) -> QueryResponse {
+    match self {
+      Query::Version => QueryResponse::Version(buffer[3], buffer[4]),
+      Query::Color(_) => QueryResponse::Color(Color::Three(buffer[2], buffer[3], buffer[4])),
+      &Query::PatternLine(pos) => {
+        let dms = (((buffer[5] as u32) << 8) | buffer[6] as u32) * 10;
+
+        QueryResponse::PatternLine {
+          color: Color::Three(buffer[2], buffer[3], buffer[4]),
+          duration: Duration::from_millis(dms as u64),
+          pos,
+        }
+      }
+      Query::PatternCount => QueryResponse::PatternCount(buffer[2]),
+    }
+  }
+}
+
+/// The typed result of a [`Query`], decoded from the device's feature report.
+#[derive(Debug, Copy, Clone)]
+pub enum QueryResponse {
+  /// The two version digits reported by the device, e.g. `(1, 3)` for firmware v1.3
+  Version(u8, u8),
+  /// The color currently loaded on the queried LED
+  Color(Color),
+  /// The color and fade time stored at the queried pattern line
+  PatternLine {
+    /// The color stored at this pattern line
+    color: Color,
+    /// The fade time stored at this pattern line
+    duration: Duration,
+    /// The pattern line position that was queried
+    pos: u8,
+  },
+  /// The number of pattern lines currently marked as in use
+  PatternCount(u8),
+}
+
 #[cfg(test)]
 mod tests {
-  use super::Message;
+  use super::{Message, Query};
+  use std::time::Duration;
 
   #[test]
   fn test_red() {
@@ -131,4 +244,47 @@ mod tests {
     let red = Message::from("off");
     assert_eq!(red.buffer()[2..5], [0x00, 0x00, 0x00])
   }
+
+  #[test]
+  fn test_save_to_flash() {
+    let save = Message::SaveToFlash;
+    assert_eq!(save.buffer(), [0x01, 0x57, 0xBE, 0xEF, 0xCA, 0xFE, 0x00, 0x00])
+  }
+
+  #[test]
+  fn test_server_tickle_timeout_split() {
+    let tickle = Message::ServerTickle {
+      on: true,
+      timeout: Duration::from_millis(6000),
+      stay_lit: true,
+      start_pos: 1,
+      end_pos: 5,
+    };
+    // 6000ms / 10 = 600 = 0x0258, split into hi/lo bytes
+    assert_eq!(tickle.buffer(), [0x01, 0x44, 0x01, 0x02, 0x58, 0x01, 0x01, 0x05])
+  }
+
+  #[test]
+  fn test_query_version_decode() {
+    let version = Query::Version;
+    let reply = [0x01, 0x76, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00];
+    match version.decode(reply) {
+      super::QueryResponse::Version(major, minor) => assert_eq!((major, minor), (1, 3)),
+      other => panic!("expected Version, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_query_pattern_line_decode() {
+    let line = Query::PatternLine(4);
+    // th/tl bytes above u16::MAX / 10 would previously overflow when decoding
+    let reply = [0x01, 0x52, 0xff, 0x00, 0x00, 0xff, 0xff, 0x04];
+    match line.decode(reply) {
+      super::QueryResponse::PatternLine { duration, pos, .. } => {
+        assert_eq!(duration, Duration::from_millis(655350));
+        assert_eq!(pos, 4);
+      }
+      other => panic!("expected PatternLine, got {:?}", other),
+    }
+  }
 }